@@ -0,0 +1,287 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This software may be used and distributed according to the terms of the
+ * GNU General Public License version 2.
+ */
+
+//! Parsing of EdenApi server URLs.
+//!
+//! In addition to strict RFC 3986 URLs, we want to accept the shorthands that
+//! users are used to typing on the command line: scp-style `user@host:path`
+//! remotes, bare `ssh://` targets, `file://` paths, and plain local paths.
+//! This module classifies the input the way `gitoxide` does before handing
+//! the (possibly rewritten) string off to `url::Url` for the actual parsing.
+
+use std::path::{Path, PathBuf};
+
+use url::Url;
+
+use crate::errors::ConfigError;
+
+/// The kind of remote that a URL (or URL-like string) was classified as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// A real URL with an explicit scheme, e.g. `https://...` or `ssh://...`.
+    Url,
+    /// An scp-style shorthand, e.g. `user@host:path/to/repo`.
+    ScpShorthand,
+    /// A `file://` URL.
+    File,
+    /// A plain local filesystem path.
+    Local,
+}
+
+/// Parse a server address into a normalized [`Url`] plus the [`Scheme`] that
+/// was detected while doing so.
+///
+/// The classification mirrors gitoxide's URL detection:
+///
+/// - If the text before the first `:` contains `@` or `.` and the `:` is not
+///   immediately followed by `//`, it's treated as scp shorthand
+///   (`user@host:path`) and rewritten to `ssh://user@host/path`.
+/// - If there's a `:` immediately followed by `//`, it's a real URL and is
+///   handed to `url::Url` unmodified (with `file://` special-cased to report
+///   `Scheme::File` rather than the generic `Scheme::Url`).
+/// - If the first `:` is immediately followed by a single `/` or `\` (no `//`
+///   authority), it's treated as a `file` URL built from the path after the
+///   colon.
+/// - Any other `:` is rejected as a relative, unsupported URL.
+/// - Input with no `:` at all is a local path, absolute or relative (it's
+///   resolved against the current directory if relative).
+///
+/// A `~` or `~user` prefix at the very start of `input` is expanded to the
+/// current (or named) user's home directory before any of the above applies.
+/// A `~` that appears later, such as in the path part of an scp-style or
+/// `ssh://` address, is left unexpanded.
+pub fn parse_url(input: &str) -> Result<(Url, Scheme), ConfigError> {
+    let input = expand_tilde(input)?;
+
+    if let Some(colon) = input.find(':') {
+        let (head, rest) = input.split_at(colon);
+        let tail = &rest[1..];
+
+        if tail.starts_with("//") {
+            // A `file://` authority is still a file URL, just spelled with
+            // an (empty) authority component; keep it out of the generic
+            // real-URL branch below so it reports `Scheme::File`.
+            if head.eq_ignore_ascii_case("file") {
+                return parse_file_url(&input);
+            }
+
+            let url = Url::parse(&input).map_err(ConfigError::InvalidUrl)?;
+            return Ok((url, Scheme::Url));
+        }
+
+        if looks_like_scp_shorthand(head) {
+            let rewritten = format!("ssh://{}/{}", head, tail.trim_start_matches(['/', '\\']));
+            let url = Url::parse(&rewritten).map_err(ConfigError::InvalidUrl)?;
+            return Ok((url, Scheme::ScpShorthand));
+        }
+
+        if tail.starts_with('/') || tail.starts_with('\\') {
+            return file_url_from_path(tail, &input);
+        }
+
+        // A colon with no recognized `//` authority, scp-shorthand host, or
+        // file-style path after it isn't a local path either -- colons
+        // aren't valid in bare local paths on the platforms we care about --
+        // so treat it as an unsupported relative URL rather than silently
+        // falling through to `parse_local_path`.
+        return Err(ConfigError::RelativeUrl(input.to_string()));
+    }
+
+    parse_local_path(&input)
+}
+
+/// Does `head` (the text before the first `:`) look like the host part of an
+/// scp-style remote (`user@host` or `host.example.com`)?
+fn looks_like_scp_shorthand(head: &str) -> bool {
+    !head.is_empty() && (head.contains('@') || head.contains('.'))
+}
+
+/// Parse `input` as a `file://...` URL with an (possibly empty) authority,
+/// e.g. `file:///tmp/repo`.
+fn parse_file_url(input: &str) -> Result<(Url, Scheme), ConfigError> {
+    let url = Url::parse(input).map_err(ConfigError::InvalidUrl)?;
+    let path = url
+        .to_file_path()
+        .map_err(|_| ConfigError::NotALocalFile(input.to_string()))?;
+    require_path(&path, input)?;
+    Ok((url, Scheme::File))
+}
+
+/// Build a file URL directly from `path`, the text following a colon that
+/// wasn't followed by `//` (e.g. the `/tmp/repo` in `bundle:/tmp/repo`).
+/// `input` is the original, unsplit text, used only for error messages.
+fn file_url_from_path(path: &str, input: &str) -> Result<(Url, Scheme), ConfigError> {
+    if path.is_empty() {
+        return Err(ConfigError::MissingRepositoryPath(input.to_string()));
+    }
+
+    let absolute = to_absolute(PathBuf::from(path), input)?;
+    let url = Url::from_file_path(&absolute)
+        .map_err(|_| ConfigError::NotALocalFile(input.to_string()))?;
+    Ok((url, Scheme::File))
+}
+
+fn parse_local_path(input: &str) -> Result<(Url, Scheme), ConfigError> {
+    if input.is_empty() {
+        return Err(ConfigError::MissingRepositoryPath(input.to_string()));
+    }
+
+    let path = to_absolute(PathBuf::from(input), input)?;
+    let url = Url::from_file_path(&path)
+        .map_err(|_| ConfigError::NotALocalFile(input.to_string()))?;
+    Ok((url, Scheme::Local))
+}
+
+fn require_path(path: &Path, input: &str) -> Result<(), ConfigError> {
+    if path.as_os_str().is_empty() {
+        return Err(ConfigError::MissingRepositoryPath(input.to_string()));
+    }
+    Ok(())
+}
+
+/// `Url::from_file_path` requires an absolute path, but we want to accept
+/// relative local paths too (e.g. `repo`, `./repo`); join them onto the
+/// current directory rather than rejecting them.
+fn to_absolute(path: PathBuf, input: &str) -> Result<PathBuf, ConfigError> {
+    if path.is_absolute() {
+        return Ok(path);
+    }
+
+    let cwd =
+        std::env::current_dir().map_err(|_| ConfigError::NotALocalFile(input.to_string()))?;
+    Ok(cwd.join(path))
+}
+
+/// Expand a leading `~` or `~user` in `input` to the relevant home directory.
+/// Inputs that don't start with `~` are returned unchanged.
+fn expand_tilde(input: &str) -> Result<String, ConfigError> {
+    if !input.starts_with('~') {
+        return Ok(input.to_string());
+    }
+
+    let (user, rest) = match input[1..].find(['/', '\\']) {
+        Some(idx) => (&input[1..1 + idx], &input[1 + idx..]),
+        None => (&input[1..], ""),
+    };
+
+    let home = if user.is_empty() {
+        dirs::home_dir()
+    } else {
+        home_dir_for_user(user)
+    }
+    .ok_or_else(|| ConfigError::MissingRepositoryPath(input.to_string()))?;
+
+    let mut expanded = home
+        .to_str()
+        .ok_or_else(|| ConfigError::NotALocalFile(input.to_string()))?
+        .to_string();
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+#[cfg(unix)]
+fn home_dir_for_user(user: &str) -> Option<PathBuf> {
+    use std::ffi::{CStr, CString};
+
+    let cuser = CString::new(user).ok()?;
+    unsafe {
+        let passwd = libc::getpwnam(cuser.as_ptr());
+        if passwd.is_null() {
+            return None;
+        }
+        let dir = CStr::from_ptr((*passwd).pw_dir).to_str().ok()?;
+        Some(PathBuf::from(dir))
+    }
+}
+
+#[cfg(not(unix))]
+fn home_dir_for_user(_user: &str) -> Option<PathBuf> {
+    // There's no portable notion of "some other user's home directory" on
+    // non-Unix platforms.
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_real_urls_untouched() {
+        let (url, scheme) = parse_url("https://example.com/repo").unwrap();
+        assert_eq!(scheme, Scheme::Url);
+        assert_eq!(url.as_str(), "https://example.com/repo");
+    }
+
+    #[test]
+    fn parses_ssh_url() {
+        let (url, scheme) = parse_url("ssh://user@example.com/repo").unwrap();
+        assert_eq!(scheme, Scheme::Url);
+        assert_eq!(url.scheme(), "ssh");
+    }
+
+    #[test]
+    fn parses_scp_shorthand_with_user() {
+        let (url, scheme) = parse_url("user@host:path/to/repo").unwrap();
+        assert_eq!(scheme, Scheme::ScpShorthand);
+        assert_eq!(url.as_str(), "ssh://user@host/path/to/repo");
+    }
+
+    #[test]
+    fn parses_scp_shorthand_with_domain() {
+        let (url, scheme) = parse_url("host.example.com:path/to/repo").unwrap();
+        assert_eq!(scheme, Scheme::ScpShorthand);
+        assert_eq!(url.as_str(), "ssh://host.example.com/path/to/repo");
+    }
+
+    #[test]
+    fn parses_file_url() {
+        let (url, scheme) = parse_url("file:///tmp/repo").unwrap();
+        assert_eq!(scheme, Scheme::File);
+        assert_eq!(url.to_file_path().unwrap(), PathBuf::from("/tmp/repo"));
+    }
+
+    #[test]
+    fn parses_file_path_without_authority() {
+        let (url, scheme) = parse_url("bundle:/tmp/repo").unwrap();
+        assert_eq!(scheme, Scheme::File);
+        assert_eq!(url.to_file_path().unwrap(), PathBuf::from("/tmp/repo"));
+    }
+
+    #[test]
+    fn parses_local_path() {
+        let (url, scheme) = parse_url("/tmp/repo").unwrap();
+        assert_eq!(scheme, Scheme::Local);
+        assert_eq!(url.to_file_path().unwrap(), PathBuf::from("/tmp/repo"));
+    }
+
+    #[test]
+    fn parses_relative_local_path() {
+        let (url, scheme) = parse_url("repo").unwrap();
+        assert_eq!(scheme, Scheme::Local);
+        assert_eq!(
+            url.to_file_path().unwrap(),
+            std::env::current_dir().unwrap().join("repo")
+        );
+    }
+
+    #[test]
+    fn rejects_relative_non_file_url() {
+        let err = parse_url("not-a-real-scheme:repo").unwrap_err();
+        assert!(matches!(err, ConfigError::RelativeUrl(_)));
+    }
+
+    #[test]
+    fn expands_home_tilde() {
+        std::env::set_var("HOME", "/home/testuser");
+        let (url, scheme) = parse_url("~/repo").unwrap();
+        assert_eq!(scheme, Scheme::Local);
+        assert_eq!(
+            url.to_file_path().unwrap(),
+            PathBuf::from("/home/testuser/repo")
+        );
+    }
+}
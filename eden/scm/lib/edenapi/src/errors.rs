@@ -38,4 +38,10 @@ pub enum ConfigError {
     InvalidUrl(#[source] url::ParseError),
     #[error("Config field '{0}' is malformed")]
     Malformed(String, #[source] anyhow::Error),
+    #[error("URL '{0}' is relative; only absolute URLs and local paths are supported")]
+    RelativeUrl(String),
+    #[error("URL '{0}' is missing a repository path")]
+    MissingRepositoryPath(String),
+    #[error("'{0}' does not refer to a local file or directory")]
+    NotALocalFile(String),
 }
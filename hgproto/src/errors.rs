@@ -0,0 +1,25 @@
+// Copyright (c) 2004-present, Facebook, Inc.
+// All Rights Reserved.
+//
+// This software may be used and distributed according to the terms of the
+// GNU General Public License version 2 or any later version.
+
+pub use failure::Error;
+
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+/// Errors specific to the wire protocol's command dispatch and
+/// capability/version negotiation.
+#[derive(Debug, Fail)]
+pub enum ProtocolError {
+    /// The peer sent a command name that isn't in the locally-known set.
+    #[fail(display = "unknown command: '{}'", _0)]
+    UnknownCommand(String),
+    /// The server's advertised protocol version is older than what the
+    /// client requires to proceed.
+    #[fail(
+        display = "server protocol version {} is older than the required minimum {}",
+        server, required
+    )]
+    IncompatibleVersion { server: u32, required: u32 },
+}
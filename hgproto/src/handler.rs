@@ -0,0 +1,40 @@
+// Copyright (c) 2004-present, Facebook, Inc.
+// All Rights Reserved.
+//
+// This software may be used and distributed according to the terms of the
+// GNU General Public License version 2 or any later version.
+
+//! Traits shared by the wire-protocol encoders.
+
+use bytes::Bytes;
+use futures::Stream;
+
+use errors::Error;
+use Response;
+
+/// A (possibly unbounded) stream of raw bytes making up an encoded response.
+pub type OutputStream = Box<Stream<Item = Bytes, Error = Error> + Send>;
+
+/// Selects how a response's payload is framed on the wire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteMode {
+    /// The response is fully buffered and its length is known up front, so
+    /// it's sent as a single `<numbytes>\n<byte>{numbytes}` frame.
+    Text,
+    /// The response is produced incrementally and may be of unknown or
+    /// unbounded size, so it's sent as a sequence of length-prefixed
+    /// `<chunklen>\n<bytes>` frames terminated by a zero-length frame.
+    Binary,
+}
+
+pub trait ResponseEncoder {
+    /// Encode `response` into the bytes that should be written to the wire.
+    fn encode(&self, response: Response) -> OutputStream;
+
+    /// The `WriteMode` that `encode` will use for `response`. Defaults to
+    /// `Text`, which is correct for any encoder that only ever produces
+    /// bounded, fully-buffered replies.
+    fn mode(&self, _response: &Response) -> WriteMode {
+        WriteMode::Text
+    }
+}
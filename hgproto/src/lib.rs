@@ -0,0 +1,26 @@
+// Copyright (c) 2004-present, Facebook, Inc.
+// All Rights Reserved.
+//
+// This software may be used and distributed according to the terms of the
+// GNU General Public License version 2 or any later version.
+
+//! Protocol encoding/decoding for the Mercurial wire protocol.
+//!
+//! `protocol` defines the abstract commands and responses exchanged between
+//! client and server; `sshproto` implements the concrete line-oriented wire
+//! encoding used over stdio/SSH.
+
+extern crate bytes;
+#[macro_use]
+extern crate failure;
+#[macro_use]
+extern crate failure_derive;
+extern crate futures;
+extern crate tokio_io;
+
+pub mod errors;
+pub mod handler;
+pub mod protocol;
+pub mod sshproto;
+
+pub use protocol::{Request, Response};
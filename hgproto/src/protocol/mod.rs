@@ -0,0 +1,15 @@
+// Copyright (c) 2004-present, Facebook, Inc.
+// All Rights Reserved.
+//
+// This software may be used and distributed according to the terms of the
+// GNU General Public License version 2 or any later version.
+
+//! The abstract commands and responses of the Mercurial wire protocol,
+//! independent of how they're actually encoded on the wire (see
+//! `sshproto` for the line-oriented SSH/stdio encoding).
+
+pub mod request;
+pub mod response;
+
+pub use self::request::Request;
+pub use self::response::Response;
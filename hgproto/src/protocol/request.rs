@@ -0,0 +1,21 @@
+// Copyright (c) 2004-present, Facebook, Inc.
+// All Rights Reserved.
+//
+// This software may be used and distributed according to the terms of the
+// GNU General Public License version 2 or any later version.
+
+//! Commands a client can issue to the server.
+
+/// A single command issued by the client, already decoded from whatever
+/// wire format it arrived in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Request {
+    /// Fetch a batch of objects, identified by an opaque argument blob
+    /// (e.g. a changegroup/pack request). The response may be large and is
+    /// streamed back rather than buffered.
+    Batch(Vec<u8>),
+    /// Ask the server which protocol version and commands it supports.
+    /// Always the first command a client should send; see
+    /// `protocol::response::Capabilities`.
+    Hello,
+}
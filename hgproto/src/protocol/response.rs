@@ -0,0 +1,101 @@
+// Copyright (c) 2004-present, Facebook, Inc.
+// All Rights Reserved.
+//
+// This software may be used and distributed according to the terms of the
+// GNU General Public License version 2 or any later version.
+
+//! Responses the server can send back to a client command.
+
+use std::collections::BTreeSet;
+
+use bytes::Bytes;
+
+use errors::{ProtocolError, Result};
+use handler::OutputStream;
+
+/// A single reply to a `Request`.
+pub enum Response {
+    /// A fully-buffered reply whose size is known up front, e.g. the result
+    /// of a metadata lookup. Encoded as a single length-prefixed frame.
+    Literal(Bytes),
+    /// A reply whose payload is produced incrementally and may be
+    /// arbitrarily large (e.g. a pack of unknown size). Encoded as a
+    /// sequence of length-prefixed chunks terminated by a zero-length
+    /// chunk, so the server never has to buffer the whole payload.
+    Stream(OutputStream),
+    /// Reply to `Request::Hello`: the server's protocol version and the
+    /// command names it advertises support for.
+    Capabilities(Capabilities),
+}
+
+/// The name of the optional capability that gates the chunked/streaming
+/// response framing (see `sshproto::response`). A client must not expect a
+/// streamed response from a server that hasn't advertised this.
+pub const STREAMING_RESPONSE: &str = "streaming-response";
+
+/// The set of commands and protocol version a peer supports, as negotiated
+/// by the `hello` command.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Monotonically increasing protocol version. Bumped whenever the wire
+    /// format or command set changes in a way clients need to know about.
+    pub version: u32,
+    /// The command names this peer understands.
+    pub commands: BTreeSet<String>,
+}
+
+impl Capabilities {
+    pub fn new(version: u32, commands: BTreeSet<String>) -> Self {
+        Capabilities { version, commands }
+    }
+
+    /// Does this capability set advertise support for `command`?
+    pub fn supports(&self, command: &str) -> bool {
+        self.commands.contains(command)
+    }
+
+    /// Fail with `ProtocolError::IncompatibleVersion` if this capability
+    /// set's version is older than `required_version`.
+    pub fn require_version(&self, required_version: u32) -> Result<()> {
+        if self.version < required_version {
+            return Err(ProtocolError::IncompatibleVersion {
+                server: self.version,
+                required: required_version,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn supports_checks_advertised_commands() {
+        let mut commands = BTreeSet::new();
+        commands.insert("batch".to_string());
+        let caps = Capabilities::new(1, commands);
+
+        assert!(caps.supports("batch"));
+        assert!(!caps.supports(STREAMING_RESPONSE));
+    }
+
+    #[test]
+    fn require_version_accepts_equal_or_newer() {
+        let caps = Capabilities::new(2, BTreeSet::new());
+        assert!(caps.require_version(2).is_ok());
+        assert!(caps.require_version(1).is_ok());
+    }
+
+    #[test]
+    fn require_version_rejects_older_server() {
+        let caps = Capabilities::new(1, BTreeSet::new());
+        let err = caps.require_version(2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "server protocol version 1 is older than the required minimum 2"
+        );
+    }
+}
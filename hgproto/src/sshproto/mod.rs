@@ -31,12 +31,23 @@
 //! ```
 //!
 //! Each command has its own encoding of the response.
+//!
+//! Responses whose size isn't known up front (e.g. a streamed pack) instead
+//! use chunked framing: a sequence of `<chunklen> '\n' <byte>{chunklen}`
+//! frames terminated by a zero-length frame. See `response::ChunkedDecoder`
+//! and `handler::WriteMode`.
+//!
+//! A client should send `hello` before anything else to learn the server's
+//! protocol version and advertised commands (see
+//! `protocol::response::Capabilities`); `HgSshCommandDecode` rejects any
+//! other unrecognized command name with `errors::ProtocolError::UnknownCommand`
+//! rather than silently dropping it.
 
 use bytes::BytesMut;
 use tokio_io::codec::Decoder;
 
 use {Request, Response};
-use handler::{OutputStream, ResponseEncoder};
+use handler::{OutputStream, ResponseEncoder, WriteMode};
 
 use errors::*;
 
@@ -52,6 +63,13 @@ impl ResponseEncoder for HgSshCommandEncode {
     fn encode(&self, response: Response) -> OutputStream {
         response::encode(response)
     }
+
+    fn mode(&self, response: &Response) -> WriteMode {
+        match *response {
+            Response::Literal(_) | Response::Capabilities(_) => WriteMode::Text,
+            Response::Stream(_) => WriteMode::Binary,
+        }
+    }
 }
 
 impl Decoder for HgSshCommandDecode {
@@ -0,0 +1,135 @@
+// Copyright (c) 2004-present, Facebook, Inc.
+// All Rights Reserved.
+//
+// This software may be used and distributed according to the terms of the
+// GNU General Public License version 2 or any later version.
+
+//! Parsing of the line-oriented SSH command protocol's requests. See the
+//! grammar documented on the `sshproto` module itself.
+
+use bytes::BytesMut;
+
+use errors::{ProtocolError, Result};
+use Request;
+
+/// Parse a single complete request from the front of `buf`, returning `None`
+/// if `buf` doesn't yet contain a full command.
+///
+/// On success, the bytes making up the parsed command (and any arguments it
+/// carries) are drained from `buf`; any trailing bytes belonging to a
+/// subsequent command are left in place for the next call. On a partial
+/// command, `buf` is left untouched so the caller can retry once more data
+/// has arrived.
+pub fn parse_request(buf: &mut BytesMut) -> Result<Option<Request>> {
+    let newline = match buf.iter().position(|&b| b == b'\n') {
+        Some(pos) => pos,
+        None => return Ok(None),
+    };
+
+    // Copy the command name out so `buf` is free to be borrowed mutably by
+    // the arms below.
+    let command = buf[..newline].to_vec();
+
+    match command.as_slice() {
+        b"batch" => parse_batch(buf, newline),
+        b"hello" => {
+            buf.split_to(newline + 1);
+            Ok(Some(Request::Hello))
+        }
+        _ => {
+            let name = String::from_utf8_lossy(&command).into_owned();
+            buf.split_to(newline + 1);
+            Err(ProtocolError::UnknownCommand(name).into())
+        }
+    }
+}
+
+/// Parse a `batch` command's single `cmds <numbytes>\n<byte>{numbytes}`
+/// key-value argument, per the grammar documented on the `sshproto` module.
+/// Returns `None` (without consuming anything) if the argument isn't fully
+/// buffered yet.
+fn parse_batch(buf: &mut BytesMut, cmd_newline: usize) -> Result<Option<Request>> {
+    let kv_start = cmd_newline + 1;
+
+    let kv_newline = match buf[kv_start..].iter().position(|&b| b == b'\n') {
+        Some(pos) => kv_start + pos,
+        None => return Ok(None),
+    };
+
+    let header = &buf[kv_start..kv_newline];
+    let space = header
+        .iter()
+        .position(|&b| b == b' ')
+        .ok_or_else(|| format_err!("malformed batch argument header"))?;
+
+    let name = String::from_utf8_lossy(&header[..space]).into_owned();
+    if name != "cmds" {
+        return Err(format_err!("unexpected batch argument '{}'", name));
+    }
+
+    let len: usize = ::std::str::from_utf8(&header[space + 1..])?.parse()?;
+    let value_start = kv_newline + 1;
+    let value_end = value_start
+        .checked_add(len)
+        .ok_or_else(|| format_err!("batch argument length overflow"))?;
+
+    if buf.len() < value_end {
+        return Ok(None);
+    }
+
+    // Drop the command line and the key-value header in one go, leaving
+    // just the value bytes at the front of `buf`.
+    buf.split_to(value_start);
+    let value = buf.split_to(len).to_vec();
+    Ok(Some(Request::Batch(value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hello() {
+        let mut buf = BytesMut::from(&b"hello\n"[..]);
+        let request = parse_request(&mut buf).unwrap();
+        assert_eq!(request, Some(Request::Hello));
+    }
+
+    #[test]
+    fn waits_for_newline() {
+        let mut buf = BytesMut::from(&b"hel"[..]);
+        let request = parse_request(&mut buf).unwrap();
+        assert_eq!(request, None);
+    }
+
+    #[test]
+    fn rejects_unknown_command() {
+        let mut buf = BytesMut::from(&b"frobnicate\n"[..]);
+        let err = parse_request(&mut buf).unwrap_err();
+        assert_eq!(err.to_string(), "unknown command: 'frobnicate'");
+    }
+
+    #[test]
+    fn parses_batch_with_args() {
+        let mut buf = BytesMut::from(&b"batch\ncmds 10\nlog;status"[..]);
+        let request = parse_request(&mut buf).unwrap();
+        assert_eq!(request, Some(Request::Batch(b"log;status".to_vec())));
+    }
+
+    #[test]
+    fn waits_for_full_batch_argument() {
+        let mut buf = BytesMut::from(&b"batch\ncmds 10\nlog;stat"[..]);
+        let request = parse_request(&mut buf).unwrap();
+        assert_eq!(request, None);
+        // Nothing should have been consumed while waiting.
+        assert_eq!(&buf[..], &b"batch\ncmds 10\nlog;stat"[..]);
+    }
+
+    #[test]
+    fn leaves_trailing_bytes_for_next_command() {
+        let mut buf = BytesMut::from(&b"batch\ncmds 3\nabchello\n"[..]);
+        let request = parse_request(&mut buf).unwrap();
+        assert_eq!(request, Some(Request::Batch(b"abc".to_vec())));
+        assert_eq!(&buf[..], &b"hello\n"[..]);
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright (c) 2004-present, Facebook, Inc.
+// All Rights Reserved.
+//
+// This software may be used and distributed according to the terms of the
+// GNU General Public License version 2 or any later version.
+
+//! Encoding (and reassembly) of responses for the line-oriented SSH command
+//! protocol.
+//!
+//! Bounded responses are sent as a single `<numbytes>\n<byte>{numbytes}`
+//! frame, same as always. Unbounded responses (e.g. a pack of unknown size)
+//! are instead sent as a sequence of `<chunklen>\n<bytes>` frames, one per
+//! non-empty `write()` on the underlying stream (empty writes produce no
+//! frame), terminated by a zero-length frame
+//! (`0\n`) so the whole payload never has to be buffered up front.
+
+use bytes::{Bytes, BytesMut};
+use futures::{Async, Poll, Stream};
+use tokio_io::codec::Decoder;
+
+use errors::{Error, Result};
+use handler::OutputStream;
+use protocol::response::Capabilities;
+use Response;
+
+pub fn encode(response: Response) -> OutputStream {
+    match response {
+        Response::Literal(bytes) => Box::new(futures::stream::once(Ok(encode_frame(&bytes)))),
+        Response::Stream(stream) => Box::new(ChunkedEncoder::new(stream)),
+        Response::Capabilities(caps) => {
+            Box::new(futures::stream::once(Ok(encode_frame(&encode_capabilities(&caps)))))
+        }
+    }
+}
+
+/// `<version>\n<space-separated command names>`
+fn encode_capabilities(caps: &Capabilities) -> Bytes {
+    let commands: Vec<&str> = caps.commands.iter().map(String::as_str).collect();
+    Bytes::from(format!("{}\n{}", caps.version, commands.join(" ")))
+}
+
+/// Parse the payload of a `Capabilities` response, as produced by
+/// `encode_capabilities`. Used by the client after sending `hello` to learn
+/// what the server supports before negotiating further commands.
+pub fn decode_capabilities(bytes: &[u8]) -> Result<Capabilities> {
+    let text = ::std::str::from_utf8(bytes)?;
+    let mut lines = text.splitn(2, '\n');
+
+    let version: u32 = lines
+        .next()
+        .unwrap_or_default()
+        .parse()
+        .map_err(|_| format_err!("malformed capabilities response: invalid version"))?;
+
+    let commands = lines
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+
+    Ok(Capabilities::new(version, commands))
+}
+
+fn encode_frame(bytes: &Bytes) -> Bytes {
+    let mut buf = BytesMut::with_capacity(bytes.len() + 20);
+    buf.extend_from_slice(bytes.len().to_string().as_bytes());
+    buf.extend_from_slice(b"\n");
+    buf.extend_from_slice(bytes);
+    buf.freeze()
+}
+
+/// Adapts a raw byte stream into the chunked `<chunklen>\n<bytes>` framing,
+/// appending the `0\n` terminator once the inner stream is exhausted.
+struct ChunkedEncoder {
+    inner: OutputStream,
+    terminated: bool,
+}
+
+impl ChunkedEncoder {
+    fn new(inner: OutputStream) -> Self {
+        ChunkedEncoder {
+            inner,
+            terminated: false,
+        }
+    }
+}
+
+impl Stream for ChunkedEncoder {
+    type Item = Bytes;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, Error> {
+        if self.terminated {
+            return Ok(Async::Ready(None));
+        }
+
+        loop {
+            match self.inner.poll()? {
+                // An empty chunk would encode to `0\n`, indistinguishable
+                // from the terminator; skip it rather than ending the
+                // stream early on a producer's legitimate empty write.
+                Async::Ready(Some(ref chunk)) if chunk.is_empty() => continue,
+                Async::Ready(Some(chunk)) => return Ok(Async::Ready(Some(encode_frame(&chunk)))),
+                Async::Ready(None) => {
+                    self.terminated = true;
+                    return Ok(Async::Ready(Some(Bytes::from_static(b"0\n"))));
+                }
+                Async::NotReady => return Ok(Async::NotReady),
+            }
+        }
+    }
+}
+
+/// One reassembled frame of a chunked response, as produced by
+/// `ChunkedDecoder`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Chunk {
+    /// A chunk of response data.
+    Data(Bytes),
+    /// The zero-length terminator frame; no more chunks will follow.
+    End,
+}
+
+/// Largest chunk payload we're willing to reassemble in one frame. A chunk
+/// length beyond this is treated as malformed input rather than trusted
+/// at face value, since a peer is free to send any value that fits in a
+/// `usize`.
+const MAX_CHUNK_LEN: usize = 128 * 1024 * 1024;
+
+/// Reassembles the chunked `<chunklen>\n<bytes>` framing produced by
+/// `encode`'s binary write mode, yielding one `Chunk` per frame.
+#[derive(Clone, Debug, Default)]
+pub struct ChunkedDecoder;
+
+impl Decoder for ChunkedDecoder {
+    type Item = Chunk;
+    type Error = Error;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Chunk>> {
+        let newline = match buf.iter().position(|&b| b == b'\n') {
+            Some(pos) => pos,
+            None => return Ok(None),
+        };
+
+        let len: usize = ::std::str::from_utf8(&buf[..newline])?.parse()?;
+
+        if len == 0 {
+            buf.split_to(newline + 1);
+            return Ok(Some(Chunk::End));
+        }
+
+        if len > MAX_CHUNK_LEN {
+            return Err(format_err!(
+                "chunk length {} exceeds maximum frame size {}",
+                len,
+                MAX_CHUNK_LEN
+            ));
+        }
+
+        // `newline + 1 + len` could otherwise overflow `usize` for an
+        // adversarial length prefix, making the `buf.len() < ...` guard
+        // below pass spuriously and panicking the following `split_to`.
+        let frame_end = (newline + 1)
+            .checked_add(len)
+            .ok_or_else(|| format_err!("chunk length {} overflows frame size", len))?;
+
+        if buf.len() < frame_end {
+            return Ok(None);
+        }
+
+        buf.split_to(newline + 1);
+        let data = buf.split_to(len).freeze();
+        Ok(Some(Chunk::Data(data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use futures::stream;
+
+    #[test]
+    fn encodes_literal_as_single_frame() {
+        let response = Response::Literal(Bytes::from_static(b"hello"));
+        let out: Vec<Bytes> = encode(response).wait().collect::<Result<_>>().unwrap();
+        assert_eq!(out, vec![Bytes::from_static(b"5\nhello")]);
+    }
+
+    #[test]
+    fn encodes_stream_as_chunks_with_terminator() {
+        let inner: OutputStream = Box::new(stream::iter_ok(vec![
+            Bytes::from_static(b"abc"),
+            Bytes::from_static(b"de"),
+        ]));
+        let response = Response::Stream(inner);
+        let out: Vec<Bytes> = encode(response).wait().collect::<Result<_>>().unwrap();
+        assert_eq!(
+            out,
+            vec![
+                Bytes::from_static(b"3\nabc"),
+                Bytes::from_static(b"2\nde"),
+                Bytes::from_static(b"0\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn encoder_skips_interior_empty_chunks() {
+        let inner: OutputStream = Box::new(stream::iter_ok(vec![
+            Bytes::from_static(b"abc"),
+            Bytes::new(),
+            Bytes::from_static(b"de"),
+        ]));
+        let response = Response::Stream(inner);
+        let out: Vec<Bytes> = encode(response).wait().collect::<Result<_>>().unwrap();
+        assert_eq!(
+            out,
+            vec![
+                Bytes::from_static(b"3\nabc"),
+                Bytes::from_static(b"2\nde"),
+                Bytes::from_static(b"0\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_chunked_frames() {
+        let mut buf = BytesMut::from(&b"3\nabc2\nde0\n"[..]);
+        let mut decoder = ChunkedDecoder::default();
+
+        assert_eq!(
+            decoder.decode(&mut buf).unwrap(),
+            Some(Chunk::Data(Bytes::from_static(b"abc")))
+        );
+        assert_eq!(
+            decoder.decode(&mut buf).unwrap(),
+            Some(Chunk::Data(Bytes::from_static(b"de")))
+        );
+        assert_eq!(decoder.decode(&mut buf).unwrap(), Some(Chunk::End));
+    }
+
+    #[test]
+    fn decode_waits_for_full_chunk() {
+        let mut buf = BytesMut::from(&b"5\nab"[..]);
+        let mut decoder = ChunkedDecoder::default();
+        assert_eq!(decoder.decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn decode_rejects_oversized_chunk_length() {
+        let mut buf = BytesMut::from(&b"18446744073709551615\nab"[..]);
+        let mut decoder = ChunkedDecoder::default();
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert!(err.to_string().contains("exceeds maximum frame size"));
+    }
+
+    #[test]
+    fn encodes_and_decodes_capabilities() {
+        let mut commands = BTreeSet::new();
+        commands.insert("batch".to_string());
+        commands.insert("hello".to_string());
+        let caps = Capabilities::new(2, commands);
+
+        let out: Vec<Bytes> = encode(Response::Capabilities(caps.clone()))
+            .wait()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(out, vec![Bytes::from_static(b"13\n2\nbatch hello")]);
+
+        let decoded = decode_capabilities(b"2\nbatch hello").unwrap();
+        assert_eq!(decoded, caps);
+    }
+}